@@ -0,0 +1,152 @@
+use clang::{Entity, Version};
+use header_translator::platform::Os;
+
+/// The minimum OS version the generated bindings are allowed to assume is
+/// running, per platform. A symbol `deprecated` at or below this version is
+/// deprecated for every consumer of the crate; a symbol `introduced` after
+/// it needs a runtime guard before it's safe to message unconditionally.
+pub fn min_deployment_target(os: Os) -> Version {
+    let (major, minor, subminor) = match os {
+        Os::MacOs => (10, 12, 0),
+        Os::IOs => (10, 0, 0),
+        Os::TvOs => (10, 0, 0),
+        Os::WatchOs => (3, 0, 0),
+    };
+    Version {
+        major,
+        minor,
+        subminor,
+    }
+}
+
+/// The introduced/deprecated/obsoleted versions (and `unavailable` flag)
+/// clang reports for a single platform, as exposed by
+/// `Entity::get_platform_availability`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OsAvailability {
+    pub introduced: Option<Version>,
+    pub deprecated: Option<Version>,
+    pub obsoleted: Option<Version>,
+    pub unavailable: bool,
+}
+
+/// What a declaration's availability on one platform should do to codegen.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Decision {
+    /// Emit the declaration normally.
+    Keep,
+    /// Drop the declaration entirely for this platform: it's either
+    /// explicitly `unavailable`, or `obsoleted` (removed again) by the SDK
+    /// being parsed.
+    Drop,
+}
+
+impl OsAvailability {
+    fn decision(&self) -> Decision {
+        if self.unavailable || self.obsoleted.is_some() {
+            Decision::Drop
+        } else {
+            Decision::Keep
+        }
+    }
+}
+
+/// Availability as reported by clang, indexed by [`Os`] since the same
+/// declaration can be introduced/deprecated at different versions (or not
+/// at all) on different platforms.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Availability {
+    per_os: std::collections::BTreeMap<Os, OsAvailability>,
+}
+
+impl Availability {
+    /// Extract availability from the api-notes/availability attributes
+    /// clang attached to `entity`.
+    ///
+    /// Requires the translation unit to have been parsed with `-fapinotes`
+    /// and `visit_implicit_attributes(true)`, as `main` already does.
+    pub fn from_entity(entity: &Entity<'_>) -> Self {
+        let mut per_os = std::collections::BTreeMap::new();
+
+        if let Some(availability) = entity.get_platform_availability() {
+            for platform in availability {
+                let Some(os) = os_from_clang_platform(&platform.platform) else {
+                    continue;
+                };
+                per_os.insert(
+                    os,
+                    OsAvailability {
+                        introduced: platform.introduced,
+                        deprecated: platform.deprecated,
+                        obsoleted: platform.obsoleted,
+                        unavailable: platform.unavailable,
+                    },
+                );
+            }
+        }
+
+        Self { per_os }
+    }
+
+    pub fn for_os(&self, os: Os) -> Option<&OsAvailability> {
+        self.per_os.get(&os)
+    }
+
+    pub fn is_unavailable(&self, os: Os) -> bool {
+        self.for_os(os).is_some_and(|a| a.unavailable)
+    }
+
+    /// Whether a declaration available on `os` should be kept or dropped
+    /// outright for that platform; see [`Decision`].
+    pub fn decision(&self, os: Os) -> Decision {
+        if self.is_unavailable(os) {
+            return Decision::Drop;
+        }
+        self.for_os(os).map_or(Decision::Keep, OsAvailability::decision)
+    }
+
+    /// `#[deprecated(note = "...")]` text for `os`, if the declaration is
+    /// deprecated at or below that platform's minimum deployment target
+    /// (i.e. every consumer of the generated bindings will already see it as
+    /// deprecated, so the attribute can be unconditional).
+    pub fn deprecated_note(&self, os: Os) -> Option<String> {
+        let deprecated = self.for_os(os)?.deprecated.clone()?;
+        if deprecated <= min_deployment_target(os) {
+            Some(format!(
+                "deprecated since {} {}.{}.{}; see Apple's API reference for a replacement",
+                os.cfg_str(),
+                deprecated.major,
+                deprecated.minor,
+                deprecated.subminor,
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// The `available!(os = version, ...)` runtime-guard argument for `os`,
+    /// if the declaration was introduced after that platform's minimum
+    /// deployment target, meaning it isn't safe to message unconditionally
+    /// when built against the crate's minimum deployment target.
+    pub fn runtime_guard(&self, os: Os) -> Option<(&'static str, Version)> {
+        let introduced = self.for_os(os)?.introduced.clone()?;
+        if introduced > min_deployment_target(os) {
+            Some((os.cfg_str(), introduced))
+        } else {
+            None
+        }
+    }
+}
+
+fn os_from_clang_platform(platform: &str) -> Option<Os> {
+    // clang's own platform names, as used in `API_AVAILABLE`/`@available`;
+    // the `_app_extension` suffixed variants share the same cfg as their
+    // base platform.
+    match platform.trim_end_matches("_app_extension") {
+        "macos" => Some(Os::MacOs),
+        "ios" => Some(Os::IOs),
+        "tvos" => Some(Os::TvOs),
+        "watchos" => Some(Os::WatchOs),
+        _ => None,
+    }
+}
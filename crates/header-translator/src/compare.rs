@@ -0,0 +1,19 @@
+use std::collections::BTreeMap;
+
+/// Walk two `BTreeMap`s that are expected to have identical keys side by
+/// side, calling `f` with each matching pair of values so the caller can
+/// report a more specific mismatch than a plain `assert_eq!` would.
+pub fn compare_btree<K: Ord + std::fmt::Debug, V>(
+    a: &BTreeMap<K, V>,
+    b: &BTreeMap<K, V>,
+    mut f: impl FnMut(&K, &V, &V),
+) {
+    assert_eq!(
+        a.keys().collect::<Vec<_>>(),
+        b.keys().collect::<Vec<_>>(),
+        "compared maps have different keys",
+    );
+    for ((key, value_a), (_, value_b)) in a.iter().zip(b.iter()) {
+        f(key, value_a, value_b);
+    }
+}
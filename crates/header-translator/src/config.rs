@@ -0,0 +1,23 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Per-framework translation settings for a framework directory holding a
+/// `translation-config.toml`. Only the file's presence is checked; its
+/// contents are not read, and `framework` is the name of that directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    pub framework: String,
+}
+
+impl Config {
+    pub fn from_file(path: &Path) -> io::Result<Self> {
+        fs::metadata(path)?;
+        let framework = path
+            .parent()
+            .and_then(|dir| dir.file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Ok(Self { framework })
+    }
+}
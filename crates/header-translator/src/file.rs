@@ -0,0 +1,187 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::config::Config;
+use crate::platform::{Abi, Os, PlatformSet};
+use crate::stmt::{Stmt, StmtKey};
+
+/// One generated `.rs` file, corresponding to a single Objective-C header.
+///
+/// Declarations are kept by [`StmtKey`] rather than in parse order, since
+/// the same declaration can turn up again (with the same or a different
+/// rendering) once results from other platforms are folded in by
+/// [`crate::Library::merge_platform`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct File {
+    /// Distinct rendered variants seen under each key. Usually just one;
+    /// more than one means the declaration's signature actually differs
+    /// between platforms (e.g. an `NSInteger`-width parameter), so each
+    /// variant is gated to the platforms it was observed with.
+    entries: BTreeMap<StmtKey, Vec<Stmt>>,
+}
+
+impl File {
+    pub fn new(_config: &Config) -> Self {
+        Self::default()
+    }
+
+    /// Record a freshly-parsed, single-platform `Stmt`.
+    pub fn add_stmt(&mut self, stmt: Stmt) {
+        self.entries.entry(stmt.key.clone()).or_default().push(stmt);
+    }
+
+    /// Fold `other`'s (single-platform) declarations into `self`, tagging
+    /// each with `(os, abi)`.
+    ///
+    /// A declaration already present in `self` under the same key *and*
+    /// with an identical rendering just gets `(os, abi)` added to its
+    /// `PlatformSet`; a new key, or a rendering that differs from what's
+    /// already there, is kept as its own distinctly-gated entry.
+    pub(crate) fn merge_platform(&mut self, other: File, os: Os, abi: Abi) {
+        for (key, variants) in other.entries {
+            let existing = self.entries.entry(key).or_default();
+            for mut variant in variants {
+                match existing.iter_mut().find(|e| e.rendered == variant.rendered) {
+                    Some(entry) => entry.platforms.insert(os, abi),
+                    None => {
+                        variant.platforms.insert(os, abi);
+                        existing.push(variant);
+                    }
+                }
+            }
+        }
+    }
+
+    pub(crate) fn compare(&self, other: &Self) {
+        assert_eq!(self, other, "file contents differ between same-platform arches");
+    }
+
+    /// Write this file's declarations to `path`, gating each one with the
+    /// `#[cfg(...)]` its accumulated [`PlatformSet`] implies relative to
+    /// `all`, plus any `#[deprecated]`/`available!` annotations.
+    pub fn output(&self, path: &Path, all: &PlatformSet) -> io::Result<()> {
+        let mut out = String::new();
+        for variants in self.entries.values() {
+            for stmt in variants {
+                if let Some(cfg) = stmt.platforms.cfg_attribute(all) {
+                    writeln!(out, "{cfg}").unwrap();
+                }
+                render_availability(&mut out, stmt);
+                writeln!(out, "{}", stmt.rendered).unwrap();
+            }
+        }
+        fs::create_dir_all(path.parent().expect("output path has a parent"))?;
+        fs::write(path.with_extension("rs"), out)
+    }
+}
+
+/// Emit `stmt`'s `#[deprecated(note = "...")]` and/or `available!(...)`
+/// runtime guard ahead of its rendered declaration.
+///
+/// `available!` is a macro `icrate` provides at runtime (mirroring how
+/// generated code already calls other `icrate`-side helpers like
+/// `msg_send!`): it checks the running OS's version against the version the
+/// symbol was introduced at, before the symbol is messaged.
+fn render_availability(out: &mut String, stmt: &Stmt) {
+    if let Some(note) = &stmt.deprecated_note {
+        writeln!(out, "#[deprecated(note = {note:?})]").unwrap();
+    }
+    if let Some((os, version)) = &stmt.runtime_guard {
+        writeln!(
+            out,
+            "available!({os} = \"{}.{}.{}\")",
+            version.major, version.minor, version.subminor,
+        )
+        .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clang::Version;
+
+    use super::*;
+
+    fn stmt(rendered: &str) -> Stmt {
+        Stmt::for_test(rendered)
+    }
+
+    #[test]
+    fn declaration_present_everywhere_is_ungated() {
+        let mut acc = File::default();
+        let mut macos = File::default();
+        macos.add_stmt(stmt("foo"));
+        acc.merge_platform(macos, Os::MacOs, Abi::Native);
+        let mut ios = File::default();
+        ios.add_stmt(stmt("foo"));
+        acc.merge_platform(ios, Os::IOs, Abi::Native);
+
+        let all: PlatformSet = [(Os::MacOs, Abi::Native), (Os::IOs, Abi::Native)]
+            .into_iter()
+            .collect();
+        let entry = &acc.entries.values().next().unwrap()[0];
+        assert_eq!(entry.platforms.cfg_attribute(&all), None);
+    }
+
+    #[test]
+    fn declaration_on_a_subset_of_platforms_is_gated() {
+        let mut acc = File::default();
+        let mut ios = File::default();
+        ios.add_stmt(stmt("only_on_ios"));
+        acc.merge_platform(ios, Os::IOs, Abi::Native);
+
+        let all: PlatformSet = [(Os::MacOs, Abi::Native), (Os::IOs, Abi::Native)]
+            .into_iter()
+            .collect();
+        let entry = &acc.entries.values().next().unwrap()[0];
+        assert_eq!(
+            entry.platforms.cfg_attribute(&all).as_deref(),
+            Some("#[cfg(target_os = \"ios\")]"),
+        );
+    }
+
+    #[test]
+    fn differing_signatures_stay_distinct_entries() {
+        let mut acc = File::default();
+        let mut macos = File::default();
+        macos.add_stmt(stmt("value: i32"));
+        acc.merge_platform(macos, Os::MacOs, Abi::Native);
+        let mut ios = File::default();
+        ios.add_stmt(stmt("value: i64"));
+        acc.merge_platform(ios, Os::IOs, Abi::Native);
+
+        let variants = acc.entries.values().next().unwrap();
+        assert_eq!(variants.len(), 2);
+    }
+
+    #[test]
+    fn deprecated_note_and_runtime_guard_are_emitted() {
+        let mut deprecated = stmt("OldThing");
+        deprecated.deprecated_note = Some("deprecated since macos 10.12.0".to_string());
+
+        let mut guarded = stmt("NewThing");
+        guarded.runtime_guard = Some((
+            "macos",
+            Version {
+                major: 11,
+                minor: 0,
+                subminor: 0,
+            },
+        ));
+
+        let mut file = File::default();
+        file.add_stmt(deprecated);
+        file.add_stmt(guarded);
+
+        let mut out = String::new();
+        for stmt in file.entries.values().next().unwrap() {
+            render_availability(&mut out, stmt);
+        }
+
+        assert!(out.contains("#[deprecated(note = \"deprecated since macos 10.12.0\")]"));
+        assert!(out.contains("available!(macos = \"11.0.0\")"));
+    }
+}
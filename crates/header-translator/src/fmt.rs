@@ -0,0 +1,11 @@
+use std::process::Command;
+
+/// Run `cargo fmt` on the given package so generated code matches the
+/// project's formatting before it's written back into the tree.
+pub fn run_cargo_fmt(package: &str) {
+    let status = Command::new("cargo")
+        .args(["fmt", "--package", package])
+        .status()
+        .expect("run `cargo fmt`");
+    assert!(status.success(), "`cargo fmt --package {package}` failed");
+}
@@ -0,0 +1,14 @@
+mod compare;
+mod config;
+mod file;
+mod fmt;
+mod library;
+pub mod platform;
+mod stmt;
+
+pub use self::compare::compare_btree;
+pub use self::config::Config;
+pub use self::file::File;
+pub use self::fmt::run_cargo_fmt;
+pub use self::library::Library;
+pub use self::stmt::Stmt;
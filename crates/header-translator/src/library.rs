@@ -0,0 +1,46 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+use crate::compare::compare_btree;
+use crate::file::File;
+use crate::platform::{Abi, Os, PlatformSet};
+
+/// Every generated file for one framework, keyed by header (file) name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Library {
+    pub files: BTreeMap<String, File>,
+}
+
+impl Library {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Assert that `self` and `other` are identical: used to check that
+    /// two different arches of the *same* platform's SDK produced byte-
+    /// identical results, which is expected to always hold.
+    pub fn compare(&self, other: &Self) {
+        compare_btree(&self.files, &other.files, |_name, file1, file2| {
+            file1.compare(file2);
+        });
+    }
+
+    /// Fold another platform's (single-arch) parse result into `self`,
+    /// tagging every declaration it contains with `(os, abi)`. See
+    /// [`File::merge_platform`] for how declarations are matched up across
+    /// platforms.
+    pub fn merge_platform(&mut self, other: Library, os: Os, abi: Abi) {
+        for (file_name, file) in other.files {
+            self.files.entry(file_name).or_default().merge_platform(file, os, abi);
+        }
+    }
+
+    /// Write every file in this library to `output_dir`, gated to `all`.
+    pub fn output(&self, output_dir: &Path, all: &PlatformSet) -> io::Result<()> {
+        for (file_name, file) in &self.files {
+            file.output(&output_dir.join(file_name), all)?;
+        }
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
+mod availability;
+
 use std::collections::BTreeMap;
 use std::io;
 use std::path::{Path, PathBuf};
@@ -5,8 +7,11 @@ use std::path::{Path, PathBuf};
 use apple_sdk::{AppleSdk, DeveloperDirectory, Platform, SdkPath, SimpleSdk};
 use clang::{Clang, Entity, EntityKind, EntityVisitResult, Index, TranslationUnit};
 
+use header_translator::platform::{Abi, Os, PlatformSet};
 use header_translator::{compare_btree, run_cargo_fmt, Config, File, Library, Stmt};
 
+use self::availability::{Availability, Decision};
+
 fn main() {
     let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
     let workspace_dir = manifest_dir.parent().unwrap();
@@ -16,15 +21,12 @@ fn main() {
     let configs = load_configs(&crate_src);
     println!("status: loaded {} configs", configs.len());
 
-    let clang = Clang::new().unwrap();
-    let index = Index::new(&clang, true, true);
-
-    let developer_dir = DeveloperDirectory::from(PathBuf::from(
+    let developer_dir = DeveloperDirectory::from(
         std::env::args_os()
-            .skip(1)
-            .next()
-            .expect("must specify developer directory as first argument"),
-    ));
+            .nth(1)
+            .map(PathBuf::from)
+            .unwrap_or_else(detect_developer_dir),
+    );
 
     let sdks: Vec<_> = developer_dir
         .platforms()
@@ -46,11 +48,20 @@ fn main() {
 
     assert_eq!(sdks.len(), 8, "should have one of each platform: {sdks:?}");
 
-    let mut final_result = None;
+    // The union of every `(Os, Abi)` we actually parse in this run; a
+    // declaration present on all of them needs no `#[cfg(...)]` gate.
+    let mut all_oses = PlatformSet::new();
+    let mut final_result: BTreeMap<String, Library> = configs
+        .iter()
+        .map(|(name, _)| (name.clone(), Library::new()))
+        .collect();
 
-    // TODO: Compare between SDKs
+    // Jobs are grouped contiguously by platform (all of one platform's
+    // `llvm_targets` before the next), which `run_parse_jobs` relies on to
+    // compare same-platform, different-arch results against each other once
+    // they're all back.
+    let mut jobs = Vec::new();
     for sdk in sdks {
-        println!("status: parsing {:?}...", sdk.platform);
         // These are found using the `get_llvm_targets.fish` helper script
         let llvm_targets: &[_] = match &sdk.platform {
             Platform::MacOsX => &[
@@ -59,50 +70,52 @@ fn main() {
                 // "i686-apple-macosx10.7.0",
             ],
             Platform::IPhoneOs => &[
-                // "arm64-apple-ios7.0.0",
+                "arm64-apple-ios7.0.0",
                 // "armv7-apple-ios7.0.0",
                 // "armv7s-apple-ios",
+            ],
+            Platform::IPhoneSimulator => &[
+                "x86_64-apple-ios7.0.0-simulator",
+                // "arm64-apple-ios7.0.0-simulator",
+                // "i386-apple-ios7.0.0-simulator",
+            ],
+            Platform::AppleTvOs => &["arm64-apple-tvos"],
+            Platform::AppleTvSimulator => &["x86_64-apple-tvos-simulator"],
+            Platform::WatchOs => &["armv7k-apple-watchos"],
+            Platform::WatchSimulator => &["x86_64-apple-watchos5.0.0-simulator"],
+            Platform::MacCatalyst => &[
+                "x86_64-apple-ios13.0-macabi",
                 // "arm64-apple-ios14.0-macabi",
-                // "x86_64-apple-ios13.0-macabi",
             ],
-            // Platform::IPhoneSimulator => &[
-            //     "arm64-apple-ios7.0.0-simulator",
-            //     "x86_64-apple-ios7.0.0-simulator",
-            //     "i386-apple-ios7.0.0-simulator",
-            // ],
-            // Platform::AppleTvOs => &["arm64-apple-tvos", "x86_64-apple-tvos"],
-            // Platform::WatchOs => &["arm64_32-apple-watchos", "armv7k-apple-watchos"],
-            // Platform::WatchSimulator => &[
-            //     "arm64-apple-watchos5.0.0-simulator",
-            //     "x86_64-apple-watchos5.0.0-simulator",
-            // ],
             _ => continue,
         };
 
-        let mut result = None;
-
-        for llvm_target in llvm_targets {
-            println!("status:     parsing llvm target {llvm_target:?}...");
-            let curr_result = parse_sdk(&index, &sdk, llvm_target, &configs);
-            println!("status:     done parsing llvm target {llvm_target:?}");
-
-            if let Some(prev_result) = &result {
-                compare_results(prev_result, &curr_result);
-            } else {
-                result = Some(curr_result);
-            }
+        let os = Os::from_platform(&sdk.platform);
+        let abi = Abi::from_platform(&sdk.platform);
+        all_oses.insert(os, abi);
+
+        for &llvm_target in llvm_targets {
+            jobs.push(ParseJob {
+                sdk: sdk.clone(),
+                llvm_target,
+                platform: sdk.platform.clone(),
+                os,
+                abi,
+            });
         }
+    }
 
-        if sdk.platform == Platform::MacOsX {
-            final_result = result;
-        }
-        println!("status: done parsing {:?}", sdk.platform);
+    let clang = Clang::new().unwrap();
+    let index = Index::new(&clang, true, true);
+
+    for (platform_result, os, abi) in run_parse_jobs(&index, jobs, &configs) {
+        merge_platform_result(&mut final_result, os, abi, platform_result);
     }
 
-    for (library, files) in final_result.expect("got a result") {
+    for (library, files) in final_result {
         println!("status: writing framework {library}...");
         let output_path = crate_src.join("generated").join(&library);
-        files.output(&output_path).unwrap();
+        files.output(&output_path, &all_oses).unwrap();
         println!("status: written framework {library}");
     }
 
@@ -110,6 +123,117 @@ fn main() {
     run_cargo_fmt("icrate");
 }
 
+/// Locate the active Xcode developer directory when none was given
+/// explicitly as the first CLI argument.
+///
+/// Honors `DEVELOPER_DIR`, then `SDKROOT` (as `xcrun` itself does), then
+/// falls back to `xcode-select -p`, so this runs out-of-the-box on a
+/// standard Xcode install and in CI where only one of the env vars is set.
+fn detect_developer_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os("DEVELOPER_DIR") {
+        return PathBuf::from(dir);
+    }
+
+    if let Some(dir) = std::env::var_os("SDKROOT")
+        .map(PathBuf::from)
+        .and_then(|sdkroot| top_level_developer_dir(&sdkroot))
+    {
+        return dir;
+    }
+
+    let output = std::process::Command::new("xcode-select")
+        .arg("-p")
+        .output()
+        .expect("run `xcode-select -p`; pass the developer directory explicitly if it's not installed");
+    assert!(
+        output.status.success(),
+        "`xcode-select -p` failed: {}",
+        String::from_utf8_lossy(&output.stderr),
+    );
+    let stdout = String::from_utf8(output.stdout).expect("xcode-select output is valid UTF-8");
+    PathBuf::from(stdout.trim())
+}
+
+/// Derive the top-level `Contents/Developer` directory from an `SDKROOT`
+/// pointing at a single SDK, e.g.
+/// `.../Contents/Developer/Platforms/MacOSX.platform/Developer/SDKs/MacOSX.sdk`.
+///
+/// `Path::ancestors()` walks deepest-first, so the *first* ancestor named
+/// `Developer` is the inner, platform-local one
+/// (`.../MacOSX.platform/Developer`), which can't enumerate the other
+/// platforms' SDKs; take the *last* match instead to land on the top-level
+/// one.
+fn top_level_developer_dir(sdkroot: &Path) -> Option<PathBuf> {
+    sdkroot
+        .ancestors()
+        .filter(|dir| dir.file_name() == Some(std::ffi::OsStr::new("Developer")))
+        .last()
+        .map(Path::to_path_buf)
+}
+
+/// One `parse_sdk` invocation to run: a single `llvm_target` of a single
+/// platform's SDK.
+struct ParseJob {
+    sdk: SdkPath,
+    llvm_target: &'static str,
+    platform: Platform,
+    os: Os,
+    abi: Abi,
+}
+
+/// Run every job in `jobs` strictly serially against a single shared
+/// `Index`, then fold jobs back together.
+///
+/// Jobs are folded in two different ways, which must not be confused:
+/// jobs sharing the same `platform` (i.e. different arches of the *same*
+/// SDK) are asserted to produce byte-identical results; jobs only sharing
+/// the same `(Os, Abi)` (e.g. device vs. simulator iOS) are NOT asserted
+/// equal -- they legitimately expose different symbols -- and are left for
+/// the caller to cfg-merge via `merge_platform_result`.
+///
+/// Jobs are not spread over worker threads: only one live `Clang` is
+/// permitted per process, so `Clang::new()` returns `Err` on every thread
+/// but the first, and `Index`/`TranslationUnit` are neither `Send` nor
+/// `Sync`, so they can't be handed to or shared with other threads either.
+/// All jobs therefore run serially through one `Index`.
+///
+/// `jobs` must be grouped contiguously by `platform`; this holds by
+/// construction since the caller only ever visits each `Platform` once and
+/// pushes all of its `llvm_targets` in that single visit. The result is one
+/// `BTreeMap<String, Library>` per distinct `platform` (tagged with its
+/// `(Os, Abi)`), in that same order.
+fn run_parse_jobs(
+    index: &Index<'_>,
+    jobs: Vec<ParseJob>,
+    configs: &BTreeMap<String, Config>,
+) -> Vec<(BTreeMap<String, Library>, Os, Abi)> {
+    let results: Vec<_> = jobs
+        .iter()
+        .map(|job| {
+            println!("status: parsing llvm target {:?}...", job.llvm_target);
+            let result = parse_sdk(index, &job.sdk, job.llvm_target, configs);
+            println!("status: done parsing llvm target {:?}", job.llvm_target);
+            result
+        })
+        .collect();
+
+    let mut platform_results = Vec::new();
+    let mut job_iter = jobs.into_iter().zip(results).peekable();
+    while let Some((job, result)) = job_iter.next() {
+        let merged = result;
+        while let Some((next_job, _)) = job_iter.peek() {
+            if next_job.platform == job.platform {
+                let (_, next_result) = job_iter.next().unwrap();
+                compare_results(&merged, &next_result);
+            } else {
+                break;
+            }
+        }
+        platform_results.push((merged, job.os, job.abi));
+    }
+    platform_results
+}
+
 fn load_configs(crate_src: &Path) -> BTreeMap<String, Config> {
     crate_src
         .read_dir()
@@ -146,6 +270,7 @@ fn parse_sdk(
     println!("status: initialized translation unit {:?}", sdk.platform);
 
     let framework_dir = sdk.path.join("System/Library/Frameworks");
+    let os = Os::from_platform(&sdk.platform);
 
     let mut preprocessing = true;
     let mut result: BTreeMap<_, _> = configs
@@ -182,7 +307,7 @@ fn parse_sdk(
                                 library
                                     .files
                                     .entry(included)
-                                    .or_insert_with(|| File::new(&config));
+                                    .or_insert_with(|| File::new(config));
                             }
                         }
                     }
@@ -198,8 +323,22 @@ fn parse_sdk(
                         }
                         preprocessing = false;
                         // No more includes / macro expansions after this line
+                        let availability = Availability::from_entity(&entity);
+
+                        // Drop declarations that are unavailable or were
+                        // obsoleted (removed again) on this platform's SDK,
+                        // rather than emitting them unconditionally.
+                        if availability.decision(os) == Decision::Drop {
+                            return EntityVisitResult::Continue;
+                        }
+
+                        let deprecated_note = availability.deprecated_note(os);
+                        let runtime_guard = availability.runtime_guard(os);
+
                         let file = library.files.get_mut(&file_name).expect("file");
-                        for stmt in Stmt::parse(&entity, &config) {
+                        for stmt in
+                            Stmt::parse(&entity, config, deprecated_note.clone(), runtime_guard.clone())
+                        {
                             file.add_stmt(stmt);
                         }
                     }
@@ -315,3 +454,26 @@ fn compare_results(data1: &BTreeMap<String, Library>, data2: &BTreeMap<String, L
     // Extra check in case our comparison above was not exaustive
     assert_eq!(data1, data2);
 }
+
+/// Fold a single platform's parse `result` into the accumulated, multi-
+/// platform `acc`.
+///
+/// Every statement already present in `acc` under the same identity (and
+/// with the same signature) just has `(os, abi)` added to its availability
+/// set. A statement only found on `(os, abi)` (or with a signature that
+/// differs from what's already in `acc`) is inserted as a new, distinctly-
+/// gated entry; see `Library::merge_platform` for how that identity is
+/// determined.
+fn merge_platform_result(
+    acc: &mut BTreeMap<String, Library>,
+    os: Os,
+    abi: Abi,
+    result: BTreeMap<String, Library>,
+) {
+    for (library_name, library) in result {
+        let acc_library = acc
+            .get_mut(&library_name)
+            .unwrap_or_else(|| panic!("library {library_name} missing from accumulator"));
+        acc_library.merge_platform(library, os, abi);
+    }
+}
@@ -0,0 +1,196 @@
+use apple_sdk::Platform;
+
+/// The operating system family a declaration is available on.
+///
+/// Simulator variants of a platform expose the same Objective-C surface as
+/// their device counterpart as far as `#[cfg(target_os = "...")]` is
+/// concerned, so they're folded into the same variant here; [`Abi`]
+/// distinguishes Catalyst further.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Os {
+    MacOs,
+    IOs,
+    TvOs,
+    WatchOs,
+}
+
+impl Os {
+    pub fn from_platform(platform: &Platform) -> Self {
+        match platform {
+            Platform::MacOsX => Self::MacOs,
+            Platform::IPhoneOs | Platform::IPhoneSimulator | Platform::MacCatalyst => Self::IOs,
+            Platform::AppleTvOs | Platform::AppleTvSimulator => Self::TvOs,
+            Platform::WatchOs | Platform::WatchSimulator => Self::WatchOs,
+            platform => panic!("platform {platform:?} not yet mapped to an Os"),
+        }
+    }
+
+    pub fn cfg_str(&self) -> &'static str {
+        match self {
+            Self::MacOs => "macos",
+            Self::IOs => "ios",
+            Self::TvOs => "tvos",
+            Self::WatchOs => "watchos",
+        }
+    }
+}
+
+/// The ABI a declaration was parsed under, for the one `Os` (iOS) that has
+/// more than one: plain device/simulator iOS, or Mac Catalyst (`macabi`),
+/// which runs the iOS frameworks on top of macOS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Abi {
+    Native,
+    Catalyst,
+}
+
+impl Abi {
+    pub fn from_platform(platform: &Platform) -> Self {
+        match platform {
+            Platform::MacCatalyst => Self::Catalyst,
+            _ => Self::Native,
+        }
+    }
+}
+
+/// The set of `(Os, Abi)` pairs a given declaration was observed on while
+/// parsing.
+///
+/// `all` is the full set the generator parsed in this run; a declaration
+/// whose `PlatformSet` equals `all` needs no `cfg` at all.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PlatformSet {
+    platforms: std::collections::BTreeSet<(Os, Abi)>,
+}
+
+impl PlatformSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, os: Os, abi: Abi) {
+        self.platforms.insert((os, abi));
+    }
+
+    fn abis_for(&self, os: Os) -> impl Iterator<Item = Abi> + '_ {
+        self.platforms
+            .iter()
+            .filter(move |(o, _)| *o == os)
+            .map(|(_, abi)| *abi)
+    }
+
+    /// Render the `#[cfg(...)]` attribute gating a declaration present on
+    /// `self` given the full set of `(Os, Abi)` pairs that were parsed.
+    ///
+    /// Returns `None` if the declaration is present everywhere, i.e. it
+    /// needs no gate at all. A declaration observed on no platform at all
+    /// gets an unconditionally-false `#[cfg(any())]`, rather than `None`,
+    /// so it's dropped instead of emitted unconditionally.
+    pub fn cfg_attribute(&self, all: &PlatformSet) -> Option<String> {
+        if self == all {
+            return None;
+        }
+
+        if self.platforms.is_empty() {
+            return Some("#[cfg(any())]".to_string());
+        }
+
+        let oses: Vec<Os> = {
+            let mut oses: Vec<_> = self.platforms.iter().map(|(os, _)| *os).collect();
+            oses.dedup();
+            oses
+        };
+
+        let clauses: Vec<String> = oses
+            .into_iter()
+            .map(|os| {
+                let both_abis_in_run =
+                    all.abis_for(os).any(|a| a == Abi::Native) && all.abis_for(os).any(|a| a == Abi::Catalyst);
+                let has_native = self.abis_for(os).any(|a| a == Abi::Native);
+                let has_catalyst = self.abis_for(os).any(|a| a == Abi::Catalyst);
+
+                let os_clause = format!("target_os = \"{}\"", os.cfg_str());
+                if !both_abis_in_run || (has_native && has_catalyst) {
+                    os_clause
+                } else if has_catalyst {
+                    format!("all({os_clause}, target_abi = \"macabi\")")
+                } else {
+                    format!("all({os_clause}, not(target_abi = \"macabi\"))")
+                }
+            })
+            .collect();
+
+        let inner = match &clauses[..] {
+            [clause] => clause.clone(),
+            clauses => format!("any({})", clauses.join(", ")),
+        };
+        Some(format!("#[cfg({inner})]"))
+    }
+}
+
+impl Extend<(Os, Abi)> for PlatformSet {
+    fn extend<T: IntoIterator<Item = (Os, Abi)>>(&mut self, iter: T) {
+        self.platforms.extend(iter);
+    }
+}
+
+impl FromIterator<(Os, Abi)> for PlatformSet {
+    fn from_iter<T: IntoIterator<Item = (Os, Abi)>>(iter: T) -> Self {
+        Self {
+            platforms: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalyst_only_gets_macabi_cfg() {
+        let all: PlatformSet = [(Os::IOs, Abi::Native), (Os::IOs, Abi::Catalyst)]
+            .into_iter()
+            .collect();
+        let catalyst_only: PlatformSet = [(Os::IOs, Abi::Catalyst)].into_iter().collect();
+
+        assert_eq!(
+            catalyst_only.cfg_attribute(&all).as_deref(),
+            Some("#[cfg(all(target_os = \"ios\", target_abi = \"macabi\"))]"),
+        );
+    }
+
+    #[test]
+    fn native_only_gets_not_macabi_cfg() {
+        let all: PlatformSet = [(Os::IOs, Abi::Native), (Os::IOs, Abi::Catalyst)]
+            .into_iter()
+            .collect();
+        let native_only: PlatformSet = [(Os::IOs, Abi::Native)].into_iter().collect();
+
+        assert_eq!(
+            native_only.cfg_attribute(&all).as_deref(),
+            Some("#[cfg(all(target_os = \"ios\", not(target_abi = \"macabi\")))]"),
+        );
+    }
+
+    #[test]
+    fn present_on_both_abis_skips_the_macabi_clause() {
+        let all: PlatformSet = [(Os::IOs, Abi::Native), (Os::IOs, Abi::Catalyst)]
+            .into_iter()
+            .collect();
+
+        // Present on both ABIs of iOS, but not every OS in `all` -- still
+        // needs an os-level gate, just not a target_abi one.
+        let all_with_macos: PlatformSet = [
+            (Os::IOs, Abi::Native),
+            (Os::IOs, Abi::Catalyst),
+            (Os::MacOs, Abi::Native),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            all.cfg_attribute(&all_with_macos).as_deref(),
+            Some("#[cfg(target_os = \"ios\")]"),
+        );
+    }
+}
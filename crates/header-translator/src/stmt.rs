@@ -0,0 +1,107 @@
+use clang::{Entity, Version};
+
+use crate::config::Config;
+use crate::platform::PlatformSet;
+
+/// Stable identity for a single parsed declaration, used to match the same
+/// declaration up across separate per-platform parses so it can be
+/// cfg-merged instead of duplicated.
+///
+/// Built from the entity's USR (clang's cross-translation-unit "Unified
+/// Symbol Resolution" string) plus its kind. A declaration clang gives no
+/// USR gets an empty one here, so the kind is what keeps such declarations
+/// of different kinds apart.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct StmtKey {
+    usr: String,
+    kind: String,
+}
+
+impl StmtKey {
+    fn from_entity(entity: &Entity<'_>) -> Self {
+        Self {
+            usr: entity.get_usr().map(|usr| usr.0).unwrap_or_default(),
+            kind: format!("{:?}", entity.get_kind()),
+        }
+    }
+}
+
+/// A single parsed Objective-C declaration (class, protocol, method,
+/// function, ...), plus the metadata needed to gate its generated Rust
+/// code to the platforms it's actually available on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stmt {
+    pub(crate) key: StmtKey,
+    /// The rendered Rust source for this declaration. Two platforms that
+    /// disagree on the signature (e.g. a parameter whose width depends on
+    /// `NSInteger`) produce a `Stmt` with the same `key` but different
+    /// `rendered` text; `File::merge_platform` keeps those as distinct,
+    /// narrowly-gated entries instead of merging them.
+    pub(crate) rendered: String,
+    /// `#[deprecated(note = "...")]` text, if this declaration is
+    /// deprecated at or below the crate's minimum deployment target.
+    pub(crate) deprecated_note: Option<String>,
+    /// The `available!(os = version)` runtime-guard argument, if this
+    /// declaration was introduced after the crate's minimum deployment
+    /// target.
+    pub(crate) runtime_guard: Option<(&'static str, Version)>,
+    /// The `(Os, Abi)` pairs this exact rendering has been observed on.
+    /// Empty until a parse result is folded into an accumulator by
+    /// `Library::merge_platform`; a bare, just-parsed `Stmt` doesn't know
+    /// yet which other platforms it'll turn up on.
+    pub(crate) platforms: PlatformSet,
+}
+
+impl Stmt {
+    /// Turn `entity` into `Stmt`s. This always yields exactly one `Stmt`,
+    /// for `entity` itself; its child declarations (e.g. the methods of a
+    /// class) are not parsed here.
+    pub fn parse(
+        entity: &Entity<'_>,
+        config: &Config,
+        deprecated_note: Option<String>,
+        runtime_guard: Option<(&'static str, Version)>,
+    ) -> impl Iterator<Item = Stmt> {
+        let stmt = Stmt {
+            key: StmtKey::from_entity(entity),
+            rendered: render_entity(entity, config),
+            deprecated_note,
+            runtime_guard,
+            platforms: PlatformSet::new(),
+        };
+        std::iter::once(stmt)
+    }
+}
+
+#[cfg(test)]
+impl Stmt {
+    /// Build a bare `Stmt` directly from a rendered signature, bypassing
+    /// `Stmt::parse`'s need for a real translation unit, so
+    /// `File`/`Library` merge logic can be exercised without libclang.
+    pub(crate) fn for_test(rendered: &str) -> Self {
+        Self {
+            key: StmtKey {
+                usr: "test".to_string(),
+                kind: "test".to_string(),
+            },
+            rendered: rendered.to_string(),
+            deprecated_note: None,
+            runtime_guard: None,
+            platforms: PlatformSet::new(),
+        }
+    }
+}
+
+/// Render `entity`'s signature as it would appear in the generated
+/// bindings.
+///
+/// This only renders enough of the signature to tell platform-dependent
+/// overloads apart (see [`Stmt::rendered`]) -- the full Objective-C to Rust
+/// translation is out of scope here.
+fn render_entity(entity: &Entity<'_>, _config: &Config) -> String {
+    let name = entity.get_name().unwrap_or_default();
+    match entity.get_type() {
+        Some(ty) => format!("{name}: {}", ty.get_display_name()),
+        None => name,
+    }
+}
@@ -22,6 +22,10 @@ impl<'a, T> NSEnumerator<'a, T> where T: INSObject {
 impl<'a, T> Iterator for NSEnumerator<'a, T> where T: INSObject {
     type Item = &'a T;
 
+    // NSEnumerator has no upfront `-count` (it also backs lazy enumerations
+    // like `-reverseObjectEnumerator`/`-keyEnumerator`), so unlike
+    // NSFastEnumerator there's no cheap way to know how many objects remain;
+    // we're stuck with the default `size_hint`/`advance_by`.
     fn next(&mut self) -> Option<&'a T> {
         unsafe {
             let obj: *mut T = msg_send![self.id, nextObject];
@@ -54,18 +58,26 @@ pub struct NSFastEnumerator<'a, C: 'a + INSFastEnumeration> {
     ptr: *const *const C::Item,
     end: *const *const C::Item,
 
+    // Count of objects not yet yielded; seeded from `-count` and decremented
+    // as `next`/`advance_by` consume the buffer, so `size_hint`/`len` are O(1).
+    remaining: usize,
+
     state: NSFastEnumerationState<C::Item>,
     buf: [*const C::Item; FAST_ENUM_BUF_SIZE],
 }
 
 impl<'a, C: INSFastEnumeration> NSFastEnumerator<'a, C> {
     fn new(object: &C) -> NSFastEnumerator<C> {
+        let remaining: c_ulong = unsafe { msg_send![object, count] };
+
         NSFastEnumerator {
             object: object,
 
             ptr: ptr::null(),
             end: ptr::null(),
 
+            remaining: remaining as usize,
+
             state: unsafe { mem::zeroed() },
             buf: [ptr::null(); FAST_ENUM_BUF_SIZE],
         }
@@ -115,10 +127,67 @@ impl<'a, C: INSFastEnumeration> Iterator for NSFastEnumerator<'a, C> {
             unsafe {
                 let obj = *self.ptr;
                 self.ptr = self.ptr.offset(1);
+                self.remaining = self.remaining.saturating_sub(1);
                 Some(&*obj)
             }
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, C: INSFastEnumeration> NSFastEnumerator<'a, C> {
+    /// Skip ahead by `n` elements without materializing references to them.
+    ///
+    /// Unlike calling `next()` in a loop, this only re-invokes
+    /// `countByEnumeratingWithState:objects:count:` when it needs to cross a
+    /// buffer boundary; skipping within the already-filled `ptr..end` window
+    /// is O(1). On success, returns `Ok(())`; if the enumeration ends early,
+    /// returns `Err(k)`, matching the unstable standard library
+    /// `Iterator::advance_by` (tracked by `iter_advance_by`): `k` is the
+    /// number of elements that could *not* be advanced over, i.e. `n - k`
+    /// elements were actually skipped. It isn't available on stable, so
+    /// this is provided as an inherent method instead of a trait override.
+    pub fn advance_by(&mut self, n: usize) -> Result<(), usize> {
+        let mut left = n;
+        loop {
+            if left == 0 {
+                return Ok(());
+            }
+
+            let buffered = unsafe { self.end.offset_from(self.ptr) as usize };
+            if buffered == 0 {
+                if !self.update_buf() {
+                    self.remaining = 0;
+                    return Err(left);
+                }
+                continue;
+            }
+
+            // Normally `buffered <= self.remaining`, but a collection's
+            // `-count` can disagree with what fast enumeration actually
+            // yields, so also cap by `self.remaining`; this keeps the
+            // subtractions below from underflowing rather than trusting
+            // that invariant.
+            let skip = buffered.min(left).min(self.remaining);
+            self.ptr = unsafe { self.ptr.offset(skip as isize) };
+            self.remaining -= skip;
+            left -= skip;
+
+            if skip == 0 {
+                self.remaining = 0;
+                return Err(left);
+            }
+        }
+    }
+}
+
+impl<'a, C: INSFastEnumeration> ExactSizeIterator for NSFastEnumerator<'a, C> {
+    fn len(&self) -> usize {
+        self.remaining
+    }
 }
 
 #[cfg(test)]
@@ -150,4 +219,21 @@ mod tests {
         let enumerator = array.enumerator();
         assert!(enumerator.enumerate().all(|(i, obj)| obj.value() == i as u32));
     }
+
+    #[test]
+    fn test_fast_enumerator_len_and_advance_by() {
+        let vec: Vec<Id<NSValue<u32>>> = (0..4).map(INSValue::from_value).collect();
+        let array: Id<NSArray<_>> = INSArray::from_vec(vec);
+
+        let mut enumerator = array.enumerator();
+        assert!(enumerator.len() == 4);
+        assert!(enumerator.advance_by(2) == Ok(()));
+        assert!(enumerator.len() == 2);
+        assert!(enumerator.next().unwrap().value() == 2);
+        // Only 1 element is actually left, so advancing by 10 skips that 1
+        // and reports the other 9 as unfulfilled (std's `advance_by`
+        // convention: `Err(k)` is the shortfall, not the amount skipped).
+        assert!(enumerator.advance_by(10) == Err(9));
+        assert!(enumerator.len() == 0);
+    }
 }